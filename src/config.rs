@@ -13,6 +13,12 @@ pub struct Charset {
     ///     ^^^^^^^ these ones
     /// ```
     pub spanning: char,
+    /// the character shown below the error span for secondary (context) labels
+    /// ```text
+    /// 0 | problem
+    ///     ------- related context
+    /// ```
+    pub spanning_secondary: char,
     /// the character shown about the span, when the error is moved to a next line
     /// ```text
     /// 0 | problem
@@ -49,6 +55,56 @@ pub struct Charset {
     ///   ^ this one
     /// ```
     pub note: char,
+    /// the character used to run a multiline label's gutter down through the lines it spans
+    /// ```text
+    /// 0 |  fn x() {
+    ///   ¦ ╭───────
+    /// 1 | │    problem
+    ///     ^ this one
+    /// 2 | │}
+    ///   ¦ ╰^ issue
+    /// ```
+    pub multi_line: char,
+    /// the character used to open a multiline label's gutter, on the row below its first line
+    /// ```text
+    /// 0 |  fn x() {
+    ///   ¦ ╭───────
+    ///     ^ this one
+    /// 1 | │    problem
+    /// 2 | │}
+    ///   ¦ ╰^ issue
+    /// ```
+    pub multi_top: char,
+    /// the character used to close a multiline label's gutter, on the row below its last line
+    /// ```text
+    /// 0 |  fn x() {
+    ///   ¦ ╭───────
+    /// 1 | │    problem
+    /// 2 | │}
+    ///   ¦ ╰^ issue
+    ///     ^ this one
+    /// ```
+    pub multi_bottom: char,
+    /// the character used to open the locator line's frame
+    /// ```text
+    ///   ╭─[name:1:1]
+    ///   ^ this one
+    /// ```
+    pub locator_top: char,
+    /// the character used to draw the locator line's frame
+    /// ```text
+    ///   ╭─[name:1:1]
+    ///    ^ this one
+    /// ```
+    pub locator_line: char,
+    /// the marker row shown in place of a run of skipped lines between two
+    /// labeled regions
+    /// ```text
+    /// 0 | problem
+    ///   ⋮
+    /// 9 | another problem
+    /// ```
+    pub fold: &'static str,
 }
 
 impl Charset {
@@ -58,11 +114,18 @@ impl Charset {
             column_line: '|',
             column_broken_line: '¦',
             spanning: '^',
+            spanning_secondary: '-',
             spanning_out: '─',
             spanning_mid: '┬',
             out_extension: '│', // not a pipe btw
             out_end: '╰',
             note: '>',
+            multi_line: '│',
+            multi_top: '╭',
+            multi_bottom: '╰',
+            locator_top: '╭',
+            locator_line: '─',
+            fold: "⋮",
         }
     }
     /// Produces a (ugly) ascii charset.
@@ -71,11 +134,18 @@ impl Charset {
             column_line: '|',
             column_broken_line: ':',
             spanning: '^',
+            spanning_secondary: '-',
             spanning_out: '-',
             spanning_mid: '.',
             out_extension: '|',
             out_end: '\\',
             note: '>',
+            multi_line: '|',
+            multi_top: '/',
+            multi_bottom: '\\',
+            locator_top: ',',
+            locator_line: '-',
+            fold: "...",
         }
     }
 }
@@ -17,6 +17,19 @@ pub mod config;
 
 /// Span of bytes in the source
 pub type Span = Range<usize>;
+/// Whether a [`Label`] marks the real error location or merely related context,
+/// mirroring rustc's primary/secondary span distinction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LabelStyle {
+    /// the actual error location; underlined inline with `^^^` in `bold_red`
+    #[default]
+    Primary,
+    /// related context; underlined inline with `---` in a dimmer color.
+    /// labels moved to a following row, or spanning several lines, are drawn
+    /// the same way as primary ones.
+    Secondary,
+}
+
 /// Label around a [`Span`]
 #[derive(Debug, Clone)]
 pub struct Label {
@@ -24,6 +37,17 @@ pub struct Label {
     pub span: Span,
     /// The message this label will draw with
     pub message: String,
+    /// Whether this is the error's primary location or secondary context
+    pub style: LabelStyle,
+}
+
+impl Label {
+    /// Mark this label as secondary context rather than the error's primary location
+    #[must_use]
+    pub const fn secondary(mut self) -> Self {
+        self.style = LabelStyle::Secondary;
+        self
+    }
 }
 
 impl<S: ToString> From<(Span, S)> for Label {
@@ -31,6 +55,7 @@ impl<S: ToString> From<(Span, S)> for Label {
         Self {
             span,
             message: m.to_string(),
+            style: LabelStyle::Primary,
         }
     }
 }
@@ -40,6 +65,7 @@ impl<S: ToString> From<(&Span, S)> for Label {
         Self {
             span: span.clone(),
             message: m.to_string(),
+            style: LabelStyle::Primary,
         }
     }
 }
@@ -51,19 +77,91 @@ pub struct Note {
     pub message: String,
 }
 
+/// A machine-applicable fix: replace `span` with `replacement`.
+#[derive(Debug, Clone)]
+pub struct Suggestion {
+    /// The span to replace
+    pub span: Span,
+    /// The text to replace it with
+    pub replacement: String,
+    /// The message this suggestion will draw with, e.g. `"help: rename to this"`
+    pub message: String,
+}
+
 /// The source text that the spans "reference"
 #[derive(Debug)]
-pub struct Source<'s>(&'s str);
+pub struct Source<'s> {
+    text: &'s str,
+    /// The name of the file this source came from, if any
+    name: Option<&'s str>,
+}
 
 impl<'s> Source<'s> {
+    /// Attach a file name to this source, so the diagnostic can print a
+    /// `[name:line:col]` locator line.
+    #[must_use]
+    pub const fn named(name: &'s str, text: &'s str) -> Self {
+        Self {
+            text,
+            name: Some(name),
+        }
+    }
+
     fn spans(&self) -> impl Iterator<Item = (&'s str, Span)> {
-        self.0.split_inclusive('\n').scan(0, |s, x| {
+        self.text.split_inclusive('\n').scan(0, |s, x| {
             let pos = *s;
             *s += x.as_bytes().len();
             let s = x.trim_matches('\n');
             Some((s, pos..pos + s.len()))
         })
     }
+
+    /// 1-based, width-aware `(line, column)` of a byte offset into this source
+    fn line_col(&self, byte: usize) -> (usize, usize) {
+        let mut line = 1;
+        let mut last_newline = 0;
+        for (i, b) in self.text.as_bytes()[..byte].iter().enumerate() {
+            if *b == b'\n' {
+                line += 1;
+                last_newline = i + 1;
+            }
+        }
+        let column = UnicodeWidthStr::width(&self.text[last_newline..byte]) + 1;
+        (line, column)
+    }
+}
+
+impl<'s> From<&'s str> for Source<'s> {
+    fn from(text: &'s str) -> Self {
+        Self { text, name: None }
+    }
+}
+
+/// The severity of a diagnostic, shown as a colored prefix before the message
+/// (e.g. `error: didn't work`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Level {
+    /// a red `error:`
+    #[default]
+    Error,
+    /// a yellow `warning:`
+    Warning,
+    /// a blue `note:`
+    Note,
+    /// a green `help:`
+    Help,
+}
+
+impl Level {
+    /// The word shown before the colon in the header, e.g. `"error"`
+    const fn prefix(self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warning => "warning",
+            Self::Note => "note",
+            Self::Help => "help",
+        }
+    }
 }
 
 /// The error builder that this crate is all about
@@ -78,20 +176,30 @@ pub struct Error<'s> {
     pub labels: Vec<Label>,
     /// Notes
     pub notes: Vec<Note>,
+    /// Machine-applicable fix-it suggestions
+    pub suggestions: Vec<Suggestion>,
     /// The config
     pub charset: Charset,
+    /// The severity of this diagnostic
+    pub level: Level,
+    /// How many lines of context to show around each labeled line before an
+    /// internal gap folds into a single marker row
+    pub context_lines: usize,
 }
 
 impl<'s> Error<'s> {
     /// Create a new error with source code attached
     #[must_use = "The error doesnt print itself"]
-    pub fn new(source: &'s str) -> Self {
+    pub fn new(source: impl Into<Source<'s>>) -> Self {
         Self {
             labels: vec![],
-            source: Source(source),
+            source: source.into(),
             notes: vec![],
+            suggestions: vec![],
             message: String::new(),
             charset: Charset::unicode(),
+            level: Level::Error,
+            context_lines: 0,
         }
     }
 
@@ -101,6 +209,19 @@ impl<'s> Error<'s> {
         self
     }
 
+    /// Sets how many lines of context to show around each labeled line
+    /// before folding a gap into a single marker row
+    pub const fn context_lines(&mut self, context_lines: usize) -> &mut Self {
+        self.context_lines = context_lines;
+        self
+    }
+
+    /// Sets the severity level, changing the colored prefix shown before the message
+    pub const fn level(&mut self, level: Level) -> &mut Self {
+        self.level = level;
+        self
+    }
+
     /// Add a message to this error
     pub fn message(&mut self, message: impl ToString) -> &mut Self {
         self.message = message.to_string();
@@ -110,7 +231,10 @@ impl<'s> Error<'s> {
     /// Add a label to this error
     pub fn label(&mut self, label: impl Into<Label>) -> &mut Self {
         let l = label.into();
-        assert!(self.source.0.len() >= l.span.end, "label must be in bounds");
+        assert!(
+            self.source.text.len() >= l.span.end,
+            "label must be in bounds"
+        );
         self.labels.push(l);
         self
     }
@@ -123,6 +247,28 @@ impl<'s> Error<'s> {
         self
     }
 
+    /// Propose a machine-applicable fix, replacing `span` with `replacement`
+    pub fn suggestion(
+        &mut self,
+        (span, replacement): (Span, impl ToString),
+        message: impl ToString,
+    ) -> &mut Self {
+        assert!(
+            self.source.text.len() >= span.end,
+            "suggestion must be in bounds"
+        );
+        assert!(
+            !self.source.text[span.start..span.end].contains('\n'),
+            "suggestion span must not cross or touch a line terminator"
+        );
+        self.suggestions.push(Suggestion {
+            span,
+            replacement: replacement.to_string(),
+            message: message.to_string(),
+        });
+        self
+    }
+
     #[cfg(test)]
     fn monochrome(&self) -> String {
         strip_str(&self.to_string()).to_string()
@@ -139,28 +285,143 @@ macro_rules! wrpeat {
 
 impl<'s> std::fmt::Display for Error<'s> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        cwriteln!(f, "{:reset}", self.message)?;
-        let lines = self.source.0.lines().count();
+        match self.level {
+            Level::Error => cwrite!(f, "{bold_red}")?,
+            Level::Warning => cwrite!(f, "{bold_yellow}")?,
+            Level::Note => cwrite!(f, "{bold_blue}")?,
+            Level::Help => cwrite!(f, "{bold_green}")?,
+        }
+        write!(f, "{}", self.level.prefix())?;
+        cwriteln!(f, "{reset}: {:reset}", self.message)?;
+        let lines = self.source.text.lines().count();
         let width = lines.ilog10() as usize + 1;
         let space = " ";
         let mut labels = self.labels.clone();
+
+        if let Some(name) = self.source.name {
+            if let Some(l) = labels
+                .iter()
+                .find(|l| l.style == LabelStyle::Primary)
+                .or_else(|| labels.first())
+            {
+                let (line, col) = self.source.line_col(l.span.start);
+                cwriteln!(
+                    f,
+                    "{space:width$} {bold_black}{}{}[{reset}{name}:{line}:{col}{bold_black}]{reset}",
+                    self.charset.locator_top,
+                    self.charset.locator_line,
+                )?;
+            }
+        }
+
+        // labels whose span crosses a line boundary get pulled out and drawn
+        // in a dedicated gutter column instead of as an inline underline.
+        let line_of = |byte: usize| {
+            self.source.text[..byte]
+                .bytes()
+                .filter(|&b| b == b'\n')
+                .count()
+        };
+        let mut multiline: Vec<(Label, usize, usize)> = vec![];
+        labels.retain(|l| {
+            if self.source.text[l.span.start..l.span.end].contains('\n') {
+                let end = l.span.end.saturating_sub(1).max(l.span.start);
+                multiline.push((l.clone(), line_of(l.span.start), line_of(end)));
+                false
+            } else {
+                true
+            }
+        });
+        multiline.sort_by_key(|(_, start, _)| *start);
+        // give each multiline label the lowest gutter column not already in
+        // use by one that's still open, so concurrently-open spans fan out
+        // into their own columns instead of colliding.
+        let mut open: Vec<Option<usize>> = vec![];
+        let multiline: Vec<(Label, usize, usize, usize)> = multiline
+            .into_iter()
+            .map(|(label, start, end)| {
+                for slot in &mut open {
+                    if slot.is_some_and(|closes| closes < start) {
+                        *slot = None;
+                    }
+                }
+                let col = match open.iter().position(Option::is_none) {
+                    Some(i) => {
+                        open[i] = Some(end);
+                        i
+                    }
+                    None => {
+                        open.push(Some(end));
+                        open.len() - 1
+                    }
+                };
+                (label, start, end, col)
+            })
+            .collect();
+        let gutter_width = open.len();
+        let gutter = |line: usize| -> String {
+            (0..gutter_width)
+                .map(|col| {
+                    let spanned = multiline
+                        .iter()
+                        .any(|(_, start, end, c)| *c == col && *start < line && line <= *end);
+                    if spanned {
+                        self.charset.multi_line
+                    } else {
+                        ' '
+                    }
+                })
+                .collect()
+        };
+
+        // lines worth showing: every labeled line, plus `context_lines` on
+        // either side of it. internal gaps bigger than that fold into a
+        // single marker row instead of vanishing with no trace.
+        let mut annotated: Vec<usize> = labels.iter().map(|l| line_of(l.span.start)).collect();
+        for (_, start, end, _) in &multiline {
+            annotated.extend(*start..=*end);
+        }
+        let mut visible = vec![false; lines.max(1)];
+        for a in annotated {
+            let lo = a.saturating_sub(self.context_lines);
+            let hi = (a + self.context_lines).min(lines.saturating_sub(1));
+            visible[lo..=hi].fill(true);
+        }
+        let mut fold_before = vec![false; lines.max(1)];
+        {
+            let mut l = 0;
+            while l < visible.len() {
+                if visible[l] {
+                    l += 1;
+                    continue;
+                }
+                let start = l;
+                while l < visible.len() && !visible[l] {
+                    l += 1;
+                }
+                if start > 0 && l < visible.len() {
+                    fold_before[start] = true;
+                }
+            }
+        }
+
         // label, width of message, width of ^^^
-        let mut found: Vec<(Label, usize, usize)> = vec![];
         for (line, (code, line_span)) in self.source.spans().enumerate() {
+            if !visible[line] {
+                if fold_before[line] {
+                    cwriteln!(f, "{space:width$} {bold_black}{}{reset}", self.charset.fold)?;
+                }
+                continue;
+            }
+            let mut candidates: Vec<(Label, usize, usize)> = vec![];
             let mut i = 0;
             while i < labels.len() {
                 if line_span.end >= labels[i].span.start && line_span.start <= labels[i].span.start
                 {
                     let candidate = labels.swap_remove(i);
-
-                    for (Label { span, .. }, ..) in &found {
-                        if span.contains(&candidate.span.start) {
-                            todo!("erorrs may not overlap")
-                        }
-                    }
                     // ^^^ length
                     let mut point = UnicodeWidthStr::width(
-                        &self.source.0[candidate.span.start - line_span.start
+                        &self.source.text[candidate.span.start - line_span.start
                             ..candidate.span.end - line_span.start],
                     );
                     if candidate.span.end == candidate.span.start {
@@ -171,65 +432,143 @@ impl<'s> std::fmt::Display for Error<'s> {
                     for chr in strip_str(candidate.message.as_str()) {
                         msglen += UnicodeWidthStr::width(chr);
                     }
-                    found.push((candidate, msglen, point));
+                    candidates.push((candidate, msglen, point));
                 } else {
                     i += 1;
                 }
             }
-            if found.is_empty() {
-                continue;
+            // labels whose spans overlap can't share an underline row, so fan
+            // them out into successive rows: each candidate joins the first
+            // row none of whose labels it overlaps, else starts a new one.
+            let overlaps =
+                |a: &Label, b: &Label| a.span.start < b.span.end && b.span.start < a.span.end;
+            let mut rows: Vec<Vec<(Label, usize, usize)>> = vec![];
+            'place: for candidate in candidates {
+                for row in &mut rows {
+                    if row.iter().all(|(l, ..)| !overlaps(l, &candidate.0)) {
+                        row.push(candidate);
+                        continue 'place;
+                    }
+                }
+                rows.push(vec![candidate]);
             }
+            let opening: Vec<_> = multiline
+                .iter()
+                .filter(|(_, start, ..)| *start == line)
+                .collect();
+            let closing: Vec<_> = multiline
+                .iter()
+                .filter(|(_, _, end, _)| *end == line)
+                .collect();
+            let gut = gutter(line);
             cwriteln!(
                 f,
-                "{bold_black}{line:width$} {} {reset}{code}",
+                "{bold_black}{line:width$} {} {reset}{gut}{code}",
                 self.charset.column_line
             )?;
-            cwrite!(
-                f,
-                "{space:width$} {:bold_black} {reset}",
-                self.charset.column_broken_line
-            )?;
 
-            // sort by width
-            found.sort_unstable_by(|(a, ..), (b, ..)| match a.span.start.cmp(&b.span.start) {
-                core::cmp::Ordering::Equal => a.span.end.cmp(&b.span.end),
-                ord => ord,
-            });
-            // keeps track of how many chars we have printed
-            let mut position = 0;
-            let mut middles = vec![];
-            for (i, (l, msglen, about)) in found.iter().map(|(v, a, b)| (v, *a, *b)).enumerate() {
-                let padding = UnicodeWidthStr::width(
-                    &self.source.0[line_span.start + position..l.span.start],
-                );
-                wrpeat!(f, padding, " ");
-                position += padding;
-
-                if found
-                    .iter()
-                    .skip(i + 1)
-                    // will this label "but into" any of the future ones if i place it here
-                    .any(|(b, ..)| l.span.start + about + msglen + 1 > b.span.start)
+            for (label, _, _, col) in &opening {
+                cwrite!(
+                    f,
+                    "{space:width$} {bold_black}{:reset} ",
+                    self.charset.column_broken_line
+                )?;
+                for (i, ch) in gut.chars().enumerate() {
+                    if i == *col {
+                        cwrite!(f, "{bold_red}{}{reset}", self.charset.multi_top)?;
+                    } else {
+                        f.write_char(ch)?;
+                    }
+                }
+                let dashes =
+                    UnicodeWidthStr::width(&self.source.text[line_span.start..label.span.start]);
+                cwrite!(f, "{bold_red}")?;
+                wrpeat!(f, dashes, self.charset.spanning_out);
+                cwriteln!(f, "{reset}")?;
+            }
+
+            for mut found in rows {
+                cwrite!(
+                    f,
+                    "{space:width$} {bold_black}{:reset} {gut}",
+                    self.charset.column_broken_line
+                )?;
+
+                // sort by start, then by end
+                found.sort_unstable_by(|(a, ..), (b, ..)| match a.span.start.cmp(&b.span.start) {
+                    core::cmp::Ordering::Equal => a.span.end.cmp(&b.span.end),
+                    ord => ord,
+                });
+                // keeps track of how many chars we have printed
+                let mut position = 0;
+                let mut middles = vec![];
+                for (i, (l, msglen, about)) in found.iter().map(|(v, a, b)| (v, *a, *b)).enumerate()
                 {
-                    let p = about.saturating_sub(1);
-                    let middle = (p + 1) / 2;
-                    cwrite!(f, "{bold_red}")?;
-                    wrpeat!(f, middle, self.charset.spanning_out);
-                    f.write_char(self.charset.spanning_mid)?;
-                    wrpeat!(f, p - middle, self.charset.spanning_out);
-                    cwrite!(f, "{reset}")?;
-                    middles.push((l, middle, msglen));
+                    let padding = UnicodeWidthStr::width(
+                        &self.source.text[line_span.start + position..l.span.start],
+                    );
+                    wrpeat!(f, padding, " ");
+                    position += padding;
+
+                    if found
+                        .iter()
+                        .skip(i + 1)
+                        // will this label "but into" any of the future ones if i place it here
+                        .any(|(b, ..)| l.span.start + about + msglen + 1 > b.span.start)
+                    {
+                        let p = about.saturating_sub(1);
+                        let middle = (p + 1) / 2;
+                        cwrite!(f, "{bold_red}")?;
+                        wrpeat!(f, middle, self.charset.spanning_out);
+                        f.write_char(self.charset.spanning_mid)?;
+                        wrpeat!(f, p - middle, self.charset.spanning_out);
+                        cwrite!(f, "{reset}")?;
+                        middles.push((l, middle, msglen));
+                        position += about;
+                        continue;
+                    }
+                    if l.style == LabelStyle::Secondary {
+                        cwrite!(f, "{dim}")?;
+                        wrpeat!(f, about, self.charset.spanning_secondary);
+                    } else {
+                        cwrite!(f, "{bold_red}")?;
+                        wrpeat!(f, about, self.charset.spanning);
+                    }
                     position += about;
-                    continue;
+                    cwrite!(f, " {:reset}", l.message)?;
+                    position += 1 + msglen;
+                }
+                writeln!(f)?;
+                extras(self, middles, line_span.clone(), f, width, self.charset)?;
+            }
+
+            // once a column's closing row has been printed, its gutter slot
+            // reads as terminated for any further closing row on this same
+            // line, rather than keeping the stale "still spanned" pipe.
+            let mut gut_chars: Vec<char> = gut.chars().collect();
+            for (label, _, _, col) in &closing {
+                cwrite!(
+                    f,
+                    "{space:width$} {bold_black}{:reset} ",
+                    self.charset.column_broken_line
+                )?;
+                for (i, ch) in gut_chars.iter().enumerate() {
+                    if i == *col {
+                        cwrite!(f, "{bold_red}{}{reset}", self.charset.multi_bottom)?;
+                    } else {
+                        f.write_char(*ch)?;
+                    }
                 }
+                let dashes =
+                    UnicodeWidthStr::width(&self.source.text[line_span.start..label.span.end])
+                        .saturating_sub(1);
                 cwrite!(f, "{bold_red}")?;
-                wrpeat!(f, about, self.charset.spanning);
-                position += about;
-                cwrite!(f, " {:reset}", l.message)?;
-                position += 1 + msglen;
+                wrpeat!(f, dashes, self.charset.spanning_out);
+                f.write_char(self.charset.spanning)?;
+                cwriteln!(f, " {:reset}", label.message)?;
+                gut_chars[*col] = ' ';
             }
-            writeln!(f)?;
-            extras(self, middles, line_span, f, width, self.charset)?;
+
             fn extras(
                 e: &Error,
                 mut unfinished: Vec<(&Label, usize, usize)>,
@@ -254,7 +593,7 @@ impl<'s> std::fmt::Display for Error<'s> {
                     let (l, connection, msglen) = unfinished[i];
 
                     let padding = UnicodeWidthStr::width(
-                        &e.source.0[line_span.start + position..l.span.start + connection],
+                        &e.source.text[line_span.start + position..l.span.start + connection],
                     );
                     wrpeat!(f, padding, " ");
                     position += padding;
@@ -280,8 +619,40 @@ impl<'s> std::fmt::Display for Error<'s> {
                 writeln!(f)?;
                 extras(e, unfinished, line_span, f, width, charset)
             }
+        }
+
+        for s in &self.suggestions {
+            let line = line_of(s.span.start);
+            let (code, line_span) = self
+                .source
+                .spans()
+                .nth(line)
+                .expect("suggestion span in bounds");
+            let mut patched = String::new();
+            patched.push_str(&code[..s.span.start - line_span.start]);
+            patched.push_str(&s.replacement);
+            patched.push_str(&code[s.span.end - line_span.start..]);
 
-            found.clear();
+            cwriteln!(f, "{space:width$} {bold_black}>{reset} {}", s.message)?;
+            cwriteln!(
+                f,
+                "{bold_green}{line:width$} {} {reset}{patched}",
+                self.charset.column_line
+            )?;
+            let padding = UnicodeWidthStr::width(&code[..s.span.start - line_span.start]);
+            cwrite!(
+                f,
+                "{space:width$} {bold_black}{:reset} ",
+                self.charset.column_broken_line
+            )?;
+            wrpeat!(f, padding, " ");
+            cwrite!(f, "{bold_green}")?;
+            wrpeat!(
+                f,
+                UnicodeWidthStr::width(s.replacement.as_str()).max(1),
+                '+'
+            );
+            cwriteln!(f, "{reset}")?;
         }
 
         for note in &self.notes {
@@ -291,6 +662,135 @@ impl<'s> std::fmt::Display for Error<'s> {
     }
 }
 
+/// Escapes a string into a JSON string literal, including the surrounding quotes.
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+impl<'s> Error<'s> {
+    /// Serializes this diagnostic to a stable JSON document, for tooling (editors,
+    /// CI) that wants to consume diagnostics without scraping the [`Display`] output.
+    ///
+    /// The shape is:
+    /// ```json
+    /// {
+    ///   "level": "error" | "warning" | "note" | "help",
+    ///   "message": "...",
+    ///   "labels": [
+    ///     {
+    ///       "message": "...",
+    ///       "style": "primary" | "secondary",
+    ///       "span": { "start": 0, "end": 5 },
+    ///       "start": { "line": 1, "column": 1 },
+    ///       "end": { "line": 1, "column": 6 }
+    ///     }
+    ///   ],
+    ///   "notes": ["..."],
+    ///   "suggestions": [
+    ///     {
+    ///       "message": "...",
+    ///       "replacement": "...",
+    ///       "span": { "start": 0, "end": 5 },
+    ///       "start": { "line": 1, "column": 1 },
+    ///       "end": { "line": 1, "column": 6 }
+    ///     }
+    ///   ]
+    /// }
+    /// ```
+    /// `span.start`/`span.end` are raw byte offsets into the source; `start`/`end`
+    /// are the corresponding 1-based, width-aware `{line, column}` pairs.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("{\"level\":");
+        out.push_str(match self.level {
+            Level::Error => "\"error\"",
+            Level::Warning => "\"warning\"",
+            Level::Note => "\"note\"",
+            Level::Help => "\"help\"",
+        });
+        write!(out, ",\"message\":{}", json_string(&self.message)).unwrap();
+        out.push_str(",\"labels\":[");
+        for (i, l) in self.labels.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            let (start_line, start_column) = self.source.line_col(l.span.start);
+            let (end_line, end_column) = self.source.line_col(l.span.end);
+            let style = match l.style {
+                LabelStyle::Primary => "primary",
+                LabelStyle::Secondary => "secondary",
+            };
+            write!(
+                out,
+                concat!(
+                    "{{\"message\":{},\"style\":\"{}\",",
+                    "\"span\":{{\"start\":{},\"end\":{}}},",
+                    "\"start\":{{\"line\":{},\"column\":{}}},",
+                    "\"end\":{{\"line\":{},\"column\":{}}}}}"
+                ),
+                json_string(&l.message),
+                style,
+                l.span.start,
+                l.span.end,
+                start_line,
+                start_column,
+                end_line,
+                end_column,
+            )
+            .unwrap();
+        }
+        out.push_str("],\"notes\":[");
+        for (i, n) in self.notes.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            out.push_str(&json_string(&n.message));
+        }
+        out.push_str("],\"suggestions\":[");
+        for (i, s) in self.suggestions.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            let (start_line, start_column) = self.source.line_col(s.span.start);
+            let (end_line, end_column) = self.source.line_col(s.span.end);
+            write!(
+                out,
+                concat!(
+                    "{{\"message\":{},\"replacement\":{},",
+                    "\"span\":{{\"start\":{},\"end\":{}}},",
+                    "\"start\":{{\"line\":{},\"column\":{}}},",
+                    "\"end\":{{\"line\":{},\"column\":{}}}}}"
+                ),
+                json_string(&s.message),
+                json_string(&s.replacement),
+                s.span.start,
+                s.span.end,
+                start_line,
+                start_column,
+                end_line,
+                end_column,
+            )
+            .unwrap();
+        }
+        out.push_str("]}");
+        out
+    }
+}
+
 #[test]
 fn display() {
     let out = Error::new("void fn x(void) -> four {\nwierd};")
@@ -303,7 +803,7 @@ fn display() {
     println!("{out}");
     assert_eq!(
         out,
-        r"attempted to use string as type
+        r"error: attempted to use string as type
 0 | void fn x(void) -> four {
   :                    ^^^^ what is 'four'?
   > help: change it to 4
@@ -322,7 +822,7 @@ fn inline() {
     println!("{out}");
     assert_eq!(
         out,
-        r"such spelling
+        r"error: such spelling
 0 | im out of this worl
   : ^^ forgot '    ^^^^ forgot d
 "
@@ -342,7 +842,7 @@ fn outline() {
     println!("{e}");
     assert_eq!(
         e,
-        r"unknown method String::new
+        r"error: unknown method String::new
 0 | Strin::nouveau().i_like_tests(3.14158)
   : --.--  ----.---- ^ caps: I    ^^^^^^^ your π is bad
   :   |        \ use new()
@@ -350,3 +850,194 @@ fn outline() {
 "
     );
 }
+
+#[test]
+fn multiline() {
+    let out = Error::new("fn foo() {\n    bar();\n}")
+        .message("unclosed thing")
+        .label((9..23, "this block"))
+        .charset(Charset::ascii())
+        .monochrome();
+    println!("{out}");
+    assert_eq!(
+        out,
+        r"error: unclosed thing
+0 |  fn foo() {
+  : /---------
+1 | |    bar();
+2 | |}
+  : \^ this block
+"
+    );
+}
+
+#[test]
+fn multiline_concurrent() {
+    // two multiline labels open at once, closing on the same line: the
+    // first one's closing row must blank out its gutter column rather
+    // than leaving it looking still-open for the second closing row.
+    let out = Error::new("fn foo() {\n  fn bar() {\n    baz();\n  }}")
+        .message("unclosed things")
+        .label((9..39, "outer block"))
+        .label((22..38, "inner block"))
+        .charset(Charset::ascii())
+        .monochrome();
+    println!("{out}");
+    assert_eq!(
+        out,
+        r"error: unclosed things
+0 |   fn foo() {
+  : / ---------
+1 | |   fn bar() {
+  : |/-----------
+2 | ||    baz();
+3 | ||  }}
+  : \|---^ outer block
+  :  \--^ inner block
+"
+    );
+}
+
+#[test]
+fn secondary() {
+    let out = Error::new("let x_value_here = y_var + some_other_far_z;")
+        .message("undefined variable `y`")
+        .label((19..24, "undefined"))
+        .label(Label::from((4..9, "ctx")).secondary())
+        .charset(Charset::ascii())
+        .monochrome();
+    println!("{out}");
+    assert_eq!(
+        out,
+        r"error: undefined variable `y`
+0 | let x_value_here = y_var + some_other_far_z;
+  :     ----- ctx      ^^^^^ undefined
+"
+    );
+}
+
+#[test]
+fn level_and_locator() {
+    let out = Error::new(Source::named("main.rs", "let x = 1;\nbad_ident;"))
+        .level(Level::Warning)
+        .message("unused expression")
+        .label((11..20, "has no effect"))
+        .charset(Charset::ascii())
+        .monochrome();
+    println!("{out}");
+    assert_eq!(
+        out,
+        r"warning: unused expression
+  ,-[main.rs:2:1]
+1 | bad_ident;
+  : ^^^^^^^^^ has no effect
+"
+    );
+}
+
+#[test]
+fn json() {
+    let out = Error::new("let x = 1;\nbad_ident;")
+        .level(Level::Warning)
+        .message("unused expression")
+        .label((11..20, "has no effect"))
+        .note("help: remove it")
+        .suggestion((11..20, ""), "help: remove it")
+        .to_json();
+    assert_eq!(
+        out,
+        r#"{"level":"warning","message":"unused expression","labels":[{"message":"has no effect","style":"primary","span":{"start":11,"end":20},"start":{"line":2,"column":1},"end":{"line":2,"column":10}}],"notes":["help: remove it"],"suggestions":[{"message":"help: remove it","replacement":"","span":{"start":11,"end":20},"start":{"line":2,"column":1},"end":{"line":2,"column":10}}]}"#
+    );
+}
+
+#[test]
+fn suggestion() {
+    let out = Error::new("let x = String::new()")
+        .message("unknown method")
+        .label((8..14, "this"))
+        .suggestion((8..14, "std::string::String"), "help: use the full path")
+        .charset(Charset::ascii())
+        .monochrome();
+    println!("{out}");
+    assert_eq!(
+        out,
+        r"error: unknown method
+0 | let x = String::new()
+  :         ^^^^^^ this
+  > help: use the full path
+0 | let x = std::string::String::new()
+  :         +++++++++++++++++++
+"
+    );
+}
+
+#[test]
+fn suggestion_end_of_line() {
+    let out = Error::new("foo;\nbar;")
+        .message("bad statement")
+        .label((0..4, "this"))
+        .suggestion((0..4, "baz;"), "help: rename")
+        .charset(Charset::ascii())
+        .monochrome();
+    assert_eq!(
+        out,
+        r"error: bad statement
+0 | foo;
+  : ^^^^ this
+  > help: rename
+0 | baz;
+  : ++++
+"
+    );
+}
+
+#[test]
+#[should_panic(expected = "must not cross or touch a line terminator")]
+fn suggestion_rejects_multiline_span() {
+    Error::new("abc\ndef").suggestion((0..4, "Z"), "x");
+}
+
+#[test]
+fn overlap() {
+    let out = Error::new("foo(bar)")
+        .message("nested problem")
+        .label((0..8, "outer"))
+        .label((4..7, "inner"))
+        .charset(Charset::ascii())
+        .monochrome();
+    assert_eq!(
+        out,
+        r"error: nested problem
+0 | foo(bar)
+  : ^^^^^^^^ outer
+  :     ^^^ inner
+"
+    );
+}
+
+#[test]
+fn fold() {
+    let src = (0..10)
+        .map(|i| format!("line{i}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let out = Error::new(src.as_str())
+        .message("two distant problems")
+        .label((0..5, "first"))
+        .label((src.len() - 5..src.len(), "second"))
+        .context_lines(1)
+        .charset(Charset::ascii())
+        .monochrome();
+    assert_eq!(
+        out,
+        r"error: two distant problems
+ 0 | line0
+   : ^^^^^ first
+ 1 | line1
+   ...
+ 8 | line8
+ 9 | line9
+   : ^^^^^ second
+"
+    );
+}